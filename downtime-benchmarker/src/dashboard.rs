@@ -0,0 +1,118 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use chrono::Local;
+use handlebars::Handlebars;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::Serialize;
+
+use crate::TargetState;
+
+const DASHBOARD_TEMPLATE: &str = include_str!("templates/dashboard.hbs");
+
+#[derive(Serialize)]
+struct TargetSnapshot {
+    name: String,
+    type_name: &'static str,
+    icon: &'static str,
+    is_failing: bool,
+    current_window_start: Option<String>,
+    total_downtime_secs: i64,
+    failure_count: usize,
+}
+
+#[derive(Serialize)]
+struct Snapshot {
+    targets: Vec<TargetSnapshot>,
+}
+
+fn snapshot(states: &[TargetState]) -> Snapshot {
+    let targets = states
+        .iter()
+        .map(|state| {
+            let total_downtime_secs = state.failures.iter().map(|f| f.duration_secs()).sum();
+            let current_window_start = if state.is_failing {
+                state
+                    .failures
+                    .last()
+                    .map(|f| f.start.format("%H:%M:%S").to_string())
+            } else {
+                None
+            };
+
+            TargetSnapshot {
+                name: state.target.name.clone(),
+                type_name: state.target.type_name(),
+                icon: state.target.icon(),
+                is_failing: state.is_failing,
+                current_window_start,
+                total_downtime_secs,
+                failure_count: state.failures.len(),
+            }
+        })
+        .collect();
+
+    Snapshot { targets }
+}
+
+async fn handle(
+    req: Request<Body>,
+    states: Arc<Mutex<Vec<TargetState>>>,
+    handlebars: Arc<Handlebars<'static>>,
+) -> Result<Response<Body>, Infallible> {
+    let snap = snapshot(&states.lock().unwrap());
+
+    match (req.method(), req.uri().path()) {
+        (&Method::GET, "/") => match handlebars.render("dashboard", &snap) {
+            Ok(html) => Ok(Response::new(Body::from(html))),
+            Err(e) => Ok(Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from(format!("template error: {}", e)))
+                .unwrap()),
+        },
+        (&Method::GET, "/metrics.json") => match serde_json::to_string(&snap) {
+            Ok(json) => Ok(Response::builder()
+                .header("content-type", "application/json")
+                .body(Body::from(json))
+                .unwrap()),
+            Err(e) => Ok(Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from(format!("serialization error: {}", e)))
+                .unwrap()),
+        },
+        _ => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::from("not found"))
+            .unwrap()),
+    }
+}
+
+/// Serves the live status dashboard (`/`) and JSON scrape endpoint
+/// (`/metrics.json`) off `states`, which the monitoring loop updates
+/// concurrently. Runs until the process exits; errors are logged and
+/// swallowed so a dashboard failure never takes down the benchmark itself.
+pub async fn serve(addr: SocketAddr, states: Arc<Mutex<Vec<TargetState>>>) {
+    let mut handlebars = Handlebars::new();
+    if let Err(e) = handlebars.register_template_string("dashboard", DASHBOARD_TEMPLATE) {
+        eprintln!("[{}] dashboard: failed to register template: {}", Local::now().format("%H:%M:%S"), e);
+        return;
+    }
+    let handlebars = Arc::new(handlebars);
+
+    let make_svc = make_service_fn(move |_conn| {
+        let states = states.clone();
+        let handlebars = handlebars.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle(req, states.clone(), handlebars.clone())
+            }))
+        }
+    });
+
+    let server = Server::bind(&addr).serve(make_svc);
+    if let Err(e) = server.await {
+        eprintln!("[{}] dashboard: server error: {}", Local::now().format("%H:%M:%S"), e);
+    }
+}
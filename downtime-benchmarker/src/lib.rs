@@ -0,0 +1,531 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+pub mod dashboard;
+pub mod report;
+
+// ANSI color codes
+pub const RED: &str = "\x1b[31m";
+pub const GREEN: &str = "\x1b[32m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const BOLD: &str = "\x1b[1m";
+pub const RESET: &str = "\x1b[0m";
+
+pub const SUPPORTED_TYPES: &[&str] = &["http", "tcp", "http3"];
+
+#[derive(Deserialize)]
+pub struct TargetsFile {
+    pub targets: Vec<RawTarget>,
+}
+
+#[derive(Deserialize)]
+pub struct RawTarget {
+    pub name: String,
+    pub r#type: String,
+    #[serde(default)]
+    pub args: HashMap<String, serde_yaml::Value>,
+}
+
+#[derive(Clone)]
+pub struct Target {
+    pub name: String,
+    pub check: Check,
+}
+
+#[derive(Clone)]
+pub enum Check {
+    Http { url: String },
+    Tcp { host: String, port: u16 },
+    Http3 { url: String },
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Target {
+    pub fn icon(&self) -> &'static str {
+        match self.check {
+            Check::Http { .. } => "🌐",
+            Check::Tcp { .. } => "🔌",
+            Check::Http3 { .. } => "⚡",
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self.check {
+            Check::Http { .. } => "http",
+            Check::Tcp { .. } => "tcp",
+            Check::Http3 { .. } => "http3",
+        }
+    }
+}
+
+pub fn validate_targets(raw_targets: Vec<RawTarget>) -> Result<Vec<Target>, String> {
+    let mut targets = Vec::with_capacity(raw_targets.len());
+
+    for (i, raw) in raw_targets.into_iter().enumerate() {
+        let idx = i + 1;
+
+        if !SUPPORTED_TYPES.contains(&raw.r#type.as_str()) {
+            return Err(format!(
+                "Target #{}: unsupported type '{}'. Supported types: {}",
+                idx,
+                raw.r#type,
+                SUPPORTED_TYPES.join(", ")
+            ));
+        }
+
+        let check = match raw.r#type.as_str() {
+            "http" => {
+                let allowed = &["url"];
+                check_unknown_args(&raw.args, allowed, idx, "http")?;
+
+                let url = require_string_arg(&raw.args, "url", idx, "http")?;
+                Check::Http { url }
+            }
+            "tcp" => {
+                let allowed = &["host", "port"];
+                check_unknown_args(&raw.args, allowed, idx, "tcp")?;
+
+                let host = require_string_arg(&raw.args, "host", idx, "tcp")?;
+                let port_val = raw.args.get("port").ok_or_else(|| {
+                    format!("Target #{} (tcp): missing required arg 'port'", idx)
+                })?;
+                let port = match port_val {
+                    serde_yaml::Value::Number(n) => n
+                        .as_u64()
+                        .and_then(|v| u16::try_from(v).ok())
+                        .ok_or_else(|| {
+                            format!(
+                                "Target #{} (tcp): 'port' must be a valid port number (1-65535)",
+                                idx
+                            )
+                        })?,
+                    _ => {
+                        return Err(format!(
+                            "Target #{} (tcp): 'port' must be a number",
+                            idx
+                        ))
+                    }
+                };
+                if port == 0 {
+                    return Err(format!(
+                        "Target #{} (tcp): 'port' must be a valid port number (1-65535)",
+                        idx
+                    ));
+                }
+                Check::Tcp { host, port }
+            }
+            "http3" => {
+                let allowed = &["url"];
+                check_unknown_args(&raw.args, allowed, idx, "http3")?;
+
+                let url = require_string_arg(&raw.args, "url", idx, "http3")?;
+                Check::Http3 { url }
+            }
+            _ => unreachable!(),
+        };
+
+        targets.push(Target {
+            name: raw.name,
+            check,
+        });
+    }
+
+    Ok(targets)
+}
+
+fn check_unknown_args(
+    args: &HashMap<String, serde_yaml::Value>,
+    allowed: &[&str],
+    idx: usize,
+    type_name: &str,
+) -> Result<(), String> {
+    for key in args.keys() {
+        if !allowed.contains(&key.as_str()) {
+            return Err(format!(
+                "Target #{} ({}): unknown arg '{}'. Allowed args: {}",
+                idx,
+                type_name,
+                key,
+                allowed.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn require_string_arg(
+    args: &HashMap<String, serde_yaml::Value>,
+    name: &str,
+    idx: usize,
+    type_name: &str,
+) -> Result<String, String> {
+    let val = args.get(name).ok_or_else(|| {
+        format!(
+            "Target #{} ({}): missing required arg '{}'",
+            idx, type_name, name
+        )
+    })?;
+    match val {
+        serde_yaml::Value::String(s) => Ok(s.clone()),
+        _ => Err(format!(
+            "Target #{} ({}): '{}' must be a string",
+            idx, type_name, name
+        )),
+    }
+}
+
+pub struct FailureWindow {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+impl FailureWindow {
+    pub fn duration_secs(&self) -> i64 {
+        (self.end - self.start).num_seconds().max(1)
+    }
+}
+
+pub struct TargetState {
+    pub target: Target,
+    pub is_failing: bool,
+    pub failures: Vec<FailureWindow>,
+}
+
+/// Applies one check result to `state`'s window bookkeeping: opens a new
+/// failure window on an OK→FAIL transition, extends the open window while
+/// still failing, and closes it on a FAIL→OK transition. This is the one
+/// place the monitoring loop's state machine lives, so it can be driven
+/// directly from tests without spinning up the full CLI loop.
+pub fn record_result(state: &mut TargetState, ok: bool, now: DateTime<Local>) {
+    if ok {
+        if state.is_failing {
+            if let Some(window) = state.failures.last_mut() {
+                window.end = now;
+            }
+            state.is_failing = false;
+        }
+    } else if state.is_failing {
+        if let Some(window) = state.failures.last_mut() {
+            window.end = now;
+        }
+    } else {
+        state.failures.push(FailureWindow { start: now, end: now });
+        state.is_failing = true;
+    }
+}
+
+/// True once at least one target has failed at least once and none of the
+/// targets that have ever failed are currently failing. Used by
+/// `--until-recovered` to stop a run right after the maintenance blip it was
+/// measuring has cleared, rather than running indefinitely.
+pub fn all_failures_recovered(states: &[TargetState]) -> bool {
+    let ever_failed = states.iter().filter(|s| !s.failures.is_empty());
+    let mut any_failed = false;
+    for state in ever_failed {
+        any_failed = true;
+        if state.is_failing {
+            return false;
+        }
+    }
+    any_failed
+}
+
+pub async fn check_target(target: &Target, timeout_secs: u64) -> bool {
+    let dur = Duration::from_secs(timeout_secs);
+    match &target.check {
+        Check::Http { url } => {
+            let client = reqwest::Client::builder()
+                .timeout(dur)
+                .danger_accept_invalid_certs(false)
+                .user_agent("downtime-benchmarker/0.1")
+                .build();
+            let client = match client {
+                Ok(c) => c,
+                Err(_) => return false,
+            };
+            match client.get(url).send().await {
+                Ok(resp) => {
+                    let status = resp.status().as_u16();
+                    (200..400).contains(&status)
+                }
+                Err(_) => false,
+            }
+        }
+        Check::Tcp { host, port } => {
+            let addr_str = if host.contains(':') {
+                // IPv6: wrap in brackets
+                format!("[{}]:{}", host, port)
+            } else {
+                format!("{}:{}", host, port)
+            };
+            timeout(dur, TcpStream::connect(addr_str.as_str()))
+                .await
+                .is_ok_and(|r| r.is_ok())
+        }
+        Check::Http3 { url } => {
+            let client = reqwest::Client::builder()
+                .timeout(dur)
+                .http3_prior_knowledge()
+                .user_agent("downtime-benchmarker/0.1")
+                .build();
+            let client = match client {
+                Ok(c) => c,
+                Err(_) => return false,
+            };
+            // Force HTTP/3 so a failed QUIC handshake counts as a failure
+            // instead of silently falling back to H2/H1.
+            match client
+                .get(url)
+                .version(reqwest::Version::HTTP_3)
+                .send()
+                .await
+            {
+                Ok(resp) => {
+                    let status = resp.status().as_u16();
+                    (200..400).contains(&status)
+                }
+                Err(_) => false,
+            }
+        }
+    }
+}
+
+pub async fn check_all(targets: &[Target], timeout_secs: u64) -> Vec<bool> {
+    let mut handles = Vec::with_capacity(targets.len());
+    for target in targets {
+        let target = target.clone();
+        handles.push(tokio::spawn(
+            async move { check_target(&target, timeout_secs).await },
+        ));
+    }
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.unwrap_or(false));
+    }
+    results
+}
+
+pub fn print_status_block(states: &[TargetState], results: &[bool]) {
+    let now = Local::now().format("%H:%M:%S");
+    println!("─── [{}] ───", now);
+    for (state, &ok) in states.iter().zip(results.iter()) {
+        let (check, color) = if ok {
+            ("✓", GREEN)
+        } else {
+            ("✗", RED)
+        };
+        println!(
+            "  {}{}{} {} {}",
+            color, check, RESET, state.target.icon(), state.target
+        );
+    }
+}
+
+pub fn print_report(states: &[TargetState]) {
+    println!();
+    println!("{}📊 Downtime Benchmarking Results{}", BOLD, RESET);
+    println!("═══════════════════════════════");
+    println!();
+
+    // Collect targets that had failures
+    let mut failed_targets: Vec<&TargetState> = states
+        .iter()
+        .filter(|s| !s.failures.is_empty())
+        .collect();
+
+    if failed_targets.is_empty() {
+        println!(
+            "{}{}✅ No downtime detected! All targets remained healthy.{}",
+            BOLD, GREEN, RESET
+        );
+        println!();
+        return;
+    }
+
+    // Sort by time of first failure
+    failed_targets.sort_by_key(|s| s.failures.first().map(|f| f.start));
+
+    let earliest = failed_targets
+        .iter()
+        .filter_map(|s| s.failures.first().map(|f| f.start))
+        .min()
+        .unwrap();
+
+    println!(
+        "{}🔴 Failures started at: {}{}",
+        RED,
+        earliest.format("%H:%M:%S"),
+        RESET
+    );
+    println!();
+    println!(
+        "{}📋 Details (sorted by time of first failure):{}",
+        BOLD, RESET
+    );
+    println!();
+
+    let mut total_downtime_secs: i64 = 0;
+
+    for state in &failed_targets {
+        let target_downtime: i64 = state.failures.iter().map(|f| f.duration_secs()).sum();
+        total_downtime_secs += target_downtime;
+        let count = state.failures.len();
+
+        println!(
+            "  {} {}{}{}",
+            state.target.icon(),
+            BOLD,
+            state.target,
+            RESET
+        );
+        println!(
+            "     {}Total downtime: {}s | {} failure(s){}",
+            RED, target_downtime, count, RESET
+        );
+
+        for (i, window) in state.failures.iter().enumerate() {
+            let dur = window.duration_secs();
+            let time = window.start.format("%H:%M:%S");
+            let connector = if i == count - 1 { "└──" } else { "├──" };
+            println!(
+                "     {} {}{:<3}s @ {}{}",
+                connector, RED, dur, time, RESET
+            );
+        }
+        println!();
+    }
+
+    println!("───────────────────────────────");
+    println!(
+        "{}{}⏱️  Total downtime: {}s{}",
+        BOLD, RED, total_downtime_secs, RESET
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn idle_state() -> TargetState {
+        TargetState {
+            target: Target {
+                name: "svc".to_string(),
+                check: Check::Tcp {
+                    host: "127.0.0.1".to_string(),
+                    port: 1,
+                },
+            },
+            is_failing: false,
+            failures: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn record_result_opens_a_window_on_ok_to_fail() {
+        let mut state = idle_state();
+        let t0 = Local::now();
+
+        record_result(&mut state, false, t0);
+
+        assert!(state.is_failing);
+        assert_eq!(state.failures.len(), 1);
+        assert_eq!(state.failures[0].start, t0);
+        assert_eq!(state.failures[0].end, t0);
+    }
+
+    #[test]
+    fn record_result_extends_rather_than_reopens_while_still_failing() {
+        let mut state = idle_state();
+        let t0 = Local::now();
+        let t1 = t0 + ChronoDuration::seconds(5);
+
+        record_result(&mut state, false, t0);
+        record_result(&mut state, false, t1);
+
+        assert!(state.is_failing);
+        assert_eq!(
+            state.failures.len(),
+            1,
+            "a second failed check while already failing must extend the open window, not open another"
+        );
+        assert_eq!(state.failures[0].start, t0);
+        assert_eq!(state.failures[0].end, t1);
+    }
+
+    #[test]
+    fn record_result_closes_the_window_on_fail_to_ok() {
+        let mut state = idle_state();
+        let t0 = Local::now();
+        let t1 = t0 + ChronoDuration::seconds(3);
+
+        record_result(&mut state, false, t0);
+        record_result(&mut state, true, t1);
+
+        assert!(!state.is_failing);
+        assert_eq!(state.failures.len(), 1);
+        assert_eq!(state.failures[0].end, t1);
+    }
+
+    #[test]
+    fn record_result_after_recovery_opens_a_new_window() {
+        let mut state = idle_state();
+        let t0 = Local::now();
+
+        record_result(&mut state, false, t0);
+        record_result(&mut state, true, t0 + ChronoDuration::seconds(1));
+        record_result(&mut state, false, t0 + ChronoDuration::seconds(10));
+
+        assert!(state.is_failing);
+        assert_eq!(state.failures.len(), 2);
+    }
+
+    #[test]
+    fn duration_secs_is_at_least_one_for_a_single_failed_check() {
+        let mut state = idle_state();
+        let t0 = Local::now();
+
+        // Fails and recovers within the same instant: start == end.
+        record_result(&mut state, false, t0);
+        record_result(&mut state, true, t0);
+
+        assert_eq!(state.failures[0].duration_secs(), 1);
+    }
+
+    #[test]
+    fn all_failures_recovered_is_false_when_nothing_has_ever_failed() {
+        let state = idle_state();
+        assert!(!all_failures_recovered(&[state]));
+    }
+
+    #[test]
+    fn all_failures_recovered_is_false_while_still_failing() {
+        let mut state = idle_state();
+        record_result(&mut state, false, Local::now());
+
+        assert!(!all_failures_recovered(&[state]));
+    }
+
+    #[test]
+    fn all_failures_recovered_is_true_once_every_failed_target_has_recovered() {
+        let mut state = idle_state();
+        let t0 = Local::now();
+        record_result(&mut state, false, t0);
+        record_result(&mut state, true, t0 + ChronoDuration::seconds(1));
+
+        assert!(all_failures_recovered(&[state]));
+    }
+}
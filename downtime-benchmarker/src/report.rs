@@ -0,0 +1,103 @@
+use serde::Serialize;
+
+use crate::TargetState;
+
+#[derive(Serialize)]
+pub struct FailureWindowReport {
+    pub start: String,
+    pub end: String,
+    pub duration_secs: i64,
+}
+
+#[derive(Serialize)]
+pub struct TargetReport {
+    pub name: String,
+    pub r#type: &'static str,
+    pub failures: Vec<FailureWindowReport>,
+    pub failure_count: usize,
+    pub total_downtime_secs: i64,
+}
+
+#[derive(Serialize)]
+pub struct Report {
+    pub targets: Vec<TargetReport>,
+    pub total_downtime_secs: i64,
+}
+
+/// Builds a serializable snapshot of `states` suitable for the `json` and
+/// `prometheus` output formats. Timestamps are rendered as RFC3339 so the
+/// output is timezone-unambiguous regardless of where it's consumed.
+pub fn build_report(states: &[TargetState]) -> Report {
+    let mut total_downtime_secs = 0;
+
+    let targets = states
+        .iter()
+        .map(|state| {
+            let failures: Vec<FailureWindowReport> = state
+                .failures
+                .iter()
+                .map(|f| FailureWindowReport {
+                    start: f.start.to_rfc3339(),
+                    end: f.end.to_rfc3339(),
+                    duration_secs: f.duration_secs(),
+                })
+                .collect();
+
+            let target_downtime_secs: i64 = failures.iter().map(|f| f.duration_secs).sum();
+            total_downtime_secs += target_downtime_secs;
+
+            TargetReport {
+                name: state.target.name.clone(),
+                r#type: state.target.type_name(),
+                failure_count: failures.len(),
+                failures,
+                total_downtime_secs: target_downtime_secs,
+            }
+        })
+        .collect();
+
+    Report {
+        targets,
+        total_downtime_secs,
+    }
+}
+
+pub fn to_json(report: &Report) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(report)
+}
+
+/// Escapes a label value per the Prometheus text exposition format: a
+/// backslash, double quote, or newline inside a target name would otherwise
+/// produce a malformed line that node_exporter's textfile collector rejects.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders textfile-exposition-format lines, one pair of metrics per target,
+/// suitable for node_exporter's textfile collector.
+pub fn to_prometheus(report: &Report) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP downtime_benchmarker_downtime_seconds_total Total seconds a target was observed to be down.\n");
+    out.push_str("# TYPE downtime_benchmarker_downtime_seconds_total counter\n");
+    for target in &report.targets {
+        out.push_str(&format!(
+            "downtime_benchmarker_downtime_seconds_total{{target=\"{}\",type=\"{}\"}} {}\n",
+            escape_label_value(&target.name), target.r#type, target.total_downtime_secs
+        ));
+    }
+
+    out.push_str("# HELP downtime_benchmarker_failures_total Number of distinct failure windows observed for a target.\n");
+    out.push_str("# TYPE downtime_benchmarker_failures_total counter\n");
+    for target in &report.targets {
+        out.push_str(&format!(
+            "downtime_benchmarker_failures_total{{target=\"{}\",type=\"{}\"}} {}\n",
+            escape_label_value(&target.name), target.r#type, target.failure_count
+        ));
+    }
+
+    out
+}
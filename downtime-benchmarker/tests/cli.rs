@@ -0,0 +1,215 @@
+use std::io::Write;
+use std::net::TcpListener;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use assert_cmd::cargo::CommandCargoExt;
+use tempfile::NamedTempFile;
+
+fn write_targets_file(yaml: &str) -> NamedTempFile {
+    let mut file = NamedTempFile::new().expect("failed to create temp targets file");
+    file.write_all(yaml.as_bytes()).unwrap();
+    file
+}
+
+fn free_tcp_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Binds and returns a listener together with its port, so the OS can't hand
+/// the same ephemeral port to a different test running concurrently in the
+/// window between probing for a free port and binding it for real.
+fn bind_up_listener() -> (TcpListener, u16) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    (listener, port)
+}
+
+/// Sends a signal to `pid` via the system `kill` binary rather than pulling
+/// in a signal-handling crate just for test harness plumbing.
+fn send_signal(pid: u32, signal: &str) {
+    let status = Command::new("kill")
+        .arg(format!("-{}", signal))
+        .arg(pid.to_string())
+        .status()
+        .expect("failed to invoke `kill`");
+    assert!(status.success(), "`kill -{} {}` failed", signal, pid);
+}
+
+#[test]
+fn exits_nonzero_when_initial_health_check_fails() {
+    let port = free_tcp_port(); // nothing is listening on it
+
+    let targets = write_targets_file(&format!(
+        "targets:\n  - name: down-service\n    type: tcp\n    args:\n      host: 127.0.0.1\n      port: {}\n",
+        port
+    ));
+
+    let mut cmd = Command::cargo_bin("downtime-benchmarker").unwrap();
+    let status = cmd
+        .arg("--target-urls")
+        .arg(targets.path())
+        .arg("--check-interval")
+        .arg("1")
+        .arg("--timeout")
+        .arg("1")
+        .status()
+        .expect("failed to run downtime-benchmarker");
+
+    assert!(!status.success());
+}
+
+// Timing-sensitive: relies on wall-clock sleeps lining up with the
+// benchmarker's check interval, so it can flake under load. Run explicitly
+// with `cargo test -- --ignored` rather than in a tight CI loop.
+#[test]
+#[ignore = "timing-sensitive wall-clock test; run with `cargo test -- --ignored`"]
+fn records_a_downtime_window_for_a_target_that_goes_down_and_recovers() {
+    let port = free_tcp_port();
+
+    // Accept connections on `port` until dropped.
+    let up = TcpListener::bind(("127.0.0.1", port)).unwrap();
+    let accepting = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+    {
+        let up = up.try_clone().unwrap();
+        let accepting = accepting.clone();
+        thread::spawn(move || {
+            up.set_nonblocking(true).unwrap();
+            while accepting.load(std::sync::atomic::Ordering::SeqCst) {
+                let _ = up.accept();
+                thread::sleep(Duration::from_millis(50));
+            }
+        });
+    }
+
+    let targets = write_targets_file(&format!(
+        "targets:\n  - name: flaky-service\n    type: tcp\n    args:\n      host: 127.0.0.1\n      port: {}\n",
+        port
+    ));
+
+    let output_file = NamedTempFile::new().unwrap();
+
+    let mut child = Command::cargo_bin("downtime-benchmarker")
+        .unwrap()
+        .arg("--target-urls")
+        .arg(targets.path())
+        .arg("--check-interval")
+        .arg("1")
+        .arg("--timeout")
+        .arg("1")
+        .arg("--output")
+        .arg("json")
+        .arg("--output-file")
+        .arg(output_file.path())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to spawn downtime-benchmarker");
+
+    // Let the initial health check and a couple of healthy checks pass.
+    thread::sleep(Duration::from_secs(2));
+
+    // Take the target down for a couple of check intervals.
+    accepting.store(false, std::sync::atomic::Ordering::SeqCst);
+    drop(up);
+    thread::sleep(Duration::from_secs(3));
+
+    // Bring it back and let it recover before stopping.
+    let recovered = TcpListener::bind(("127.0.0.1", port)).unwrap();
+    thread::sleep(Duration::from_secs(2));
+
+    send_signal(child.id(), "INT");
+    let status = child.wait().expect("child did not exit");
+    drop(recovered);
+
+    assert!(status.success());
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(output_file.path()).unwrap())
+            .expect("report file did not contain valid JSON");
+
+    // Gated loosely (>=1 rather than an exact count) since extra check
+    // intervals landing right at the up/down boundary can split or merge
+    // windows depending on scheduling.
+    let target_report = &report["targets"][0];
+    assert_eq!(target_report["name"], "flaky-service");
+    assert!(target_report["failure_count"].as_u64().unwrap() >= 1);
+    assert!(target_report["total_downtime_secs"].as_i64().unwrap() >= 1);
+}
+
+#[test]
+fn stops_on_its_own_when_duration_elapses() {
+    let (_up, port) = bind_up_listener();
+
+    let targets = write_targets_file(&format!(
+        "targets:\n  - name: steady-service\n    type: tcp\n    args:\n      host: 127.0.0.1\n      port: {}\n",
+        port
+    ));
+
+    let status = Command::cargo_bin("downtime-benchmarker")
+        .unwrap()
+        .arg("--target-urls")
+        .arg(targets.path())
+        .arg("--check-interval")
+        .arg("1")
+        .arg("--timeout")
+        .arg("1")
+        .arg("--duration")
+        .arg("2")
+        .stdout(Stdio::null())
+        .status()
+        .expect("failed to run downtime-benchmarker");
+
+    assert!(status.success());
+}
+
+#[test]
+fn sigterm_still_triggers_the_final_report() {
+    let (_up, port) = bind_up_listener();
+
+    let targets = write_targets_file(&format!(
+        "targets:\n  - name: steady-service\n    type: tcp\n    args:\n      host: 127.0.0.1\n      port: {}\n",
+        port
+    ));
+
+    let output_file = NamedTempFile::new().unwrap();
+
+    let mut child = Command::cargo_bin("downtime-benchmarker")
+        .unwrap()
+        .arg("--target-urls")
+        .arg(targets.path())
+        .arg("--check-interval")
+        .arg("1")
+        .arg("--timeout")
+        .arg("1")
+        .arg("--output")
+        .arg("json")
+        .arg("--output-file")
+        .arg(output_file.path())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to spawn downtime-benchmarker");
+
+    // Let the initial health check pass and the monitoring loop start.
+    thread::sleep(Duration::from_secs(2));
+
+    send_signal(child.id(), "TERM");
+    let status = child.wait().expect("child did not exit");
+
+    assert!(
+        status.success(),
+        "a SIGTERM'd run should still exit cleanly after writing its report"
+    );
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(output_file.path()).unwrap())
+            .expect("SIGTERM should not skip the close-windows + report step");
+
+    assert_eq!(report["targets"][0]["name"], "steady-service");
+}